@@ -0,0 +1,154 @@
+use crate::Gpu;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+const MIB: u64 = 1024 * 1024;
+
+/// Renders `template` against `gpu`, substituting `$vendor`, `$model`,
+/// `$utilization`, `$temperature`, `$vram_used`, `$vram_total`, and `$power`
+/// placeholders with that GPU's live stats. VRAM is auto-scaled to MiB/GiB.
+///
+/// Intended for embedding in status-bar blocks, e.g.
+/// `render(" $model $utilization% $vram_used/$vram_total ", gpu)`.
+#[cfg(feature = "gpu_info")]
+pub fn render(template: &str, gpu: &dyn Gpu) -> String {
+  let info = gpu.info();
+
+  template
+    .replace("$vendor", gpu.vendor())
+    .replace("$model", gpu.model())
+    .replace("$utilization", &info.load_pct().to_string())
+    .replace("$temperature", &info.temperature().to_string())
+    .replace("$vram_used", &format_bytes(info.used_vram()))
+    .replace("$vram_total", &format_bytes(info.total_vram()))
+    .replace("$power", &info.power_usage_watts().to_string())
+}
+
+fn format_bytes(bytes: u64) -> String {
+  if bytes >= GIB {
+    format!("{:.1}GiB", bytes as f64 / GIB as f64)
+  } else {
+    format!("{:.0}MiB", bytes as f64 / MIB as f64)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fmt;
+
+  use super::*;
+  use crate::GpuInfo;
+  #[cfg(feature = "fan_control")]
+  use crate::FanControl;
+
+  #[test]
+  fn format_bytes_zero() {
+    assert_eq!(format_bytes(0), "0MiB");
+  }
+
+  #[test]
+  fn format_bytes_exactly_one_mib() {
+    assert_eq!(format_bytes(1024 * 1024), "1MiB");
+  }
+
+  #[test]
+  fn format_bytes_just_under_gib() {
+    assert_eq!(format_bytes(1023 * 1024 * 1024), "1023MiB");
+  }
+
+  #[test]
+  fn format_bytes_exactly_one_gib() {
+    assert_eq!(format_bytes(GIB), "1.0GiB");
+  }
+
+  #[test]
+  fn format_bytes_fractional_gib() {
+    assert_eq!(format_bytes(GIB + GIB / 2), "1.5GiB");
+  }
+
+  struct StubGpu;
+
+  impl fmt::Debug for StubGpu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      f.debug_struct("StubGpu").finish()
+    }
+  }
+
+  struct StubGpuInfo;
+
+  impl GpuInfo for StubGpuInfo {
+    fn total_vram(&self) -> u64 {
+      8 * GIB
+    }
+
+    fn used_vram(&self) -> u64 {
+      512 * MIB
+    }
+
+    fn load_pct(&self) -> u32 {
+      42
+    }
+
+    fn temperature(&self) -> u32 {
+      65
+    }
+
+    fn power_usage_watts(&self) -> u32 {
+      120
+    }
+
+    fn core_clock_mhz(&self) -> u32 {
+      1500
+    }
+
+    fn memory_clock_mhz(&self) -> u32 {
+      1000
+    }
+  }
+
+  impl Gpu for StubGpu {
+    fn vendor(&self) -> &str {
+      "Stub"
+    }
+
+    fn model(&self) -> &str {
+      "Stub Model"
+    }
+
+    fn family(&self) -> &str {
+      "StubFamily"
+    }
+
+    fn device_id(&self) -> &u32 {
+      &0x1234
+    }
+
+    fn pci_bus_id(&self) -> Option<&str> {
+      None
+    }
+
+    fn info(&self) -> Box<dyn GpuInfo> {
+      Box::new(StubGpuInfo)
+    }
+
+    #[cfg(feature = "fan_control")]
+    fn fan(&self) -> Result<Box<dyn FanControl>, Box<dyn std::error::Error>> {
+      Err("fan control is not implemented for StubGpu".into())
+    }
+
+    #[cfg(feature = "snapshot")]
+    fn snapshot(&self) -> crate::snapshot::GpuSnapshot {
+      unimplemented!("not exercised by the format tests")
+    }
+  }
+
+  #[test]
+  fn render_substitutes_every_placeholder() {
+    let gpu = StubGpu;
+    let rendered = render(
+      " $vendor $model $utilization% $temperature°C $vram_used/$vram_total $power W ",
+      &gpu,
+    );
+
+    assert_eq!(rendered, " Stub Stub Model 42% 65°C 512MiB/8.0GiB 120 W ");
+  }
+}
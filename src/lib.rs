@@ -0,0 +1,87 @@
+use std::{error::Error, fmt::Debug};
+
+#[cfg(target_os = "linux")]
+pub mod linux;
+
+#[cfg(feature = "nvidia")]
+pub mod nvidia;
+
+#[cfg(feature = "gpu_info")]
+pub mod format;
+
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+
+/// Common surface every backend (AMD, NVIDIA, ...) implements so callers can
+/// work with GPUs without caring which vendor actually owns the device.
+pub trait Gpu: Debug {
+  fn vendor(&self) -> &str;
+  fn model(&self) -> &str;
+  fn family(&self) -> &str;
+  fn device_id(&self) -> &u32;
+
+  /// PCI bus id (e.g. `0000:03:00.0`), for disambiguating between multiple
+  /// adapters from the same or different vendors. `None` if the backend has
+  /// no notion of one.
+  fn pci_bus_id(&self) -> Option<&str>;
+
+  #[cfg(feature = "gpu_info")]
+  fn info(&self) -> Box<dyn GpuInfo>;
+
+  #[cfg(feature = "fan_control")]
+  fn fan(&self) -> Result<Box<dyn FanControl>, Box<dyn Error>>;
+
+  #[cfg(feature = "snapshot")]
+  fn snapshot(&self) -> snapshot::GpuSnapshot;
+}
+
+/// Live, pollable stats for a [`Gpu`]. Kept separate from `Gpu` itself so
+/// cheap identity lookups don't pay for opening sensor handles.
+#[cfg(feature = "gpu_info")]
+pub trait GpuInfo {
+  fn total_vram(&self) -> u64;
+  fn used_vram(&self) -> u64;
+  fn load_pct(&self) -> u32;
+  fn temperature(&self) -> u32;
+  fn power_usage_watts(&self) -> u32;
+  fn core_clock_mhz(&self) -> u32;
+  fn memory_clock_mhz(&self) -> u32;
+}
+
+/// Manual fan control for a [`Gpu`]. Implementations that enable manual mode
+/// should restore automatic control on drop so a crashed controller process
+/// doesn't leave the fan pinned at whatever PWM it was last set to.
+#[cfg(feature = "fan_control")]
+pub trait FanControl {
+  fn fan_rpm(&self) -> Option<u32>;
+  fn pwm(&self) -> Option<u8>;
+  fn temps_c(&self) -> Vec<u32>;
+
+  fn set_pwm(&mut self, pwm: u8) -> Result<(), Box<dyn Error>>;
+
+  /// Reads the hottest available temperature sensor, linearly interpolates
+  /// between the two curve points surrounding it, and applies the result.
+  /// `points` need not be sorted. Below the lowest point the PWM is clamped
+  /// to its value; above the highest, likewise.
+  fn set_fan_curve(&mut self, points: &[(u32, u8)]) -> Result<(), Box<dyn Error>>;
+}
+
+/// Every GPU this crate can see, AMD and NVIDIA alike, behind one
+/// cross-vendor enumeration call. A backend that fails to initialize (e.g.
+/// NVML missing on an AMD-only box) is silently skipped rather than failing
+/// the whole call.
+pub fn detect_gpus() -> Vec<Box<dyn Gpu>> {
+  let mut gpus: Vec<Box<dyn Gpu>> = Vec::new();
+
+  #[cfg(target_os = "linux")]
+  if let Ok(amd_gpus) = linux::amd::all_gpus() {
+    gpus.extend(amd_gpus.into_iter().map(|gpu| Box::new(gpu) as Box<dyn Gpu>));
+  }
+
+  #[cfg(feature = "nvidia")]
+  if let Ok(nvidia_gpus) = nvidia::all_gpus() {
+    gpus.extend(nvidia_gpus.into_iter().map(|gpu| Box::new(gpu) as Box<dyn Gpu>));
+  }
+
+  gpus
+}
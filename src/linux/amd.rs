@@ -1,10 +1,12 @@
-use std::{error::Error, fmt::Debug, fs::File, os::fd::AsRawFd, path::PathBuf, rc::Rc};
+use std::{error::Error, fmt::Debug, fs, fs::File, os::fd::AsRawFd, path::Path, path::PathBuf, rc::Rc};
 
 use libdrm_amdgpu_sys::{
   AMDGPU::{self, DeviceHandle, GPU_INFO, SENSOR_INFO::SENSOR_TYPE},
   LibDrmAmdgpu,
 };
 
+#[cfg(feature = "fan_control")]
+use crate::FanControl;
 use crate::{Gpu, GpuInfo};
 
 pub struct AmdGpu {
@@ -15,6 +17,8 @@ pub struct AmdGpu {
   model: String,
   family: String,
   device_id: u32,
+  pci_bus_id: String,
+  is_apu: bool,
 }
 
 impl Debug for AmdGpu {
@@ -25,10 +29,19 @@ impl Debug for AmdGpu {
       .field("model", &self.model)
       .field("family", &self.family)
       .field("device_id", &self.device_id)
+      .field("pci_bus_id", &self.pci_bus_id)
+      .field("is_apu", &self.is_apu)
       .finish()
   }
 }
 
+impl AmdGpu {
+  #[cfg(feature = "fan_control")]
+  pub fn fan_control(&self) -> Result<AmdFanControl, Box<dyn Error>> {
+    AmdFanControl::new(locate_hwmon(&self.path)?)
+  }
+}
+
 impl Gpu for AmdGpu {
   fn vendor(&self) -> &str {
     &self.vendor
@@ -46,12 +59,70 @@ impl Gpu for AmdGpu {
     &self.device_id
   }
 
+  fn pci_bus_id(&self) -> Option<&str> {
+    Some(&self.pci_bus_id)
+  }
+
   #[cfg(feature = "gpu_info")]
   fn info(&self) -> Box<dyn GpuInfo> {
     Box::new(AmdGpuInfo {
       device: self.device.clone(),
     })
   }
+
+  #[cfg(feature = "fan_control")]
+  fn fan(&self) -> Result<Box<dyn FanControl>, Box<dyn Error>> {
+    Ok(Box::new(self.fan_control()?))
+  }
+
+  #[cfg(feature = "snapshot")]
+  fn snapshot(&self) -> crate::snapshot::GpuSnapshot {
+    use crate::snapshot::{GpuMemorySnapshot, GpuSensorSnapshot, GpuSnapshot, MemoryHeap};
+
+    let meminfo = self.device.memory_info().ok();
+
+    let memory = GpuMemorySnapshot {
+      vram: meminfo
+        .as_ref()
+        .map(|m| MemoryHeap {
+          total: Some(m.vram.total_heap_size),
+          used: Some(m.vram.heap_usage),
+        })
+        .unwrap_or_default(),
+      visible_vram: meminfo
+        .as_ref()
+        .map(|m| MemoryHeap {
+          total: Some(m.cpu_accessible_vram.total_heap_size),
+          used: Some(m.cpu_accessible_vram.heap_usage),
+        })
+        .unwrap_or_default(),
+      gtt: meminfo
+        .as_ref()
+        .map(|m| MemoryHeap {
+          total: Some(m.gtt.total_heap_size),
+          used: Some(m.gtt.heap_usage),
+        })
+        .unwrap_or_default(),
+    };
+
+    let sensors = GpuSensorSnapshot {
+      load_pct: self.device.sensor_info(SENSOR_TYPE::GPU_LOAD).ok(),
+      temperature_c: self.device.sensor_info(SENSOR_TYPE::GPU_TEMP).ok(),
+      power_usage_watts: self.device.sensor_info(SENSOR_TYPE::GPU_AVG_POWER).ok(),
+      core_clock_mhz: self.device.sensor_info(SENSOR_TYPE::GPU_SCLK).ok(),
+      memory_clock_mhz: self.device.sensor_info(SENSOR_TYPE::GPU_MCLK).ok(),
+    };
+
+    GpuSnapshot {
+      vendor: self.vendor.clone(),
+      model: self.model.clone(),
+      family: self.family.clone(),
+      device_id: self.device_id,
+      pci_bus_id: Some(self.pci_bus_id.clone()),
+      memory,
+      sensors,
+    }
+  }
 }
 
 #[cfg(feature = "gpu_info")]
@@ -92,22 +163,32 @@ impl GpuInfo for AmdGpuInfo {
       .sensor_info(SENSOR_TYPE::GPU_TEMP)
       .unwrap_or_default()
   }
-}
 
-// https://github.com/Umio-Yasuno/libdrm-amdgpu-sys-rs/blob/main/examples/amdgpu_info.rs
-pub fn active_gpu() -> Result<AmdGpu, Box<dyn Error>> {
-  let drm = LibDrmAmdgpu::new().map_err(|_| "Could not initialize libdrm")?;
-  let pci_devs = AMDGPU::get_all_amdgpu_pci_bus();
+  fn power_usage_watts(&self) -> u32 {
+    self
+      .device
+      .sensor_info(SENSOR_TYPE::GPU_AVG_POWER)
+      .unwrap_or_default()
+  }
 
-  if pci_devs.is_empty() {
-    return Err("No AMD GPU found".into());
+  fn core_clock_mhz(&self) -> u32 {
+    self
+      .device
+      .sensor_info(SENSOR_TYPE::GPU_SCLK)
+      .unwrap_or_default()
   }
 
-  // TODO: first() is almost definitely not the right way to do this
-  let dev_path = match pci_devs.first() {
-    Some(pci_dev) => pci_dev.get_drm_render_path()?,
-    None => return Err("No AMD GPU found".into()),
-  };
+  fn memory_clock_mhz(&self) -> u32 {
+    self
+      .device
+      .sensor_info(SENSOR_TYPE::GPU_MCLK)
+      .unwrap_or_default()
+  }
+}
+
+// https://github.com/Umio-Yasuno/libdrm-amdgpu-sys-rs/blob/main/examples/amdgpu_info.rs
+fn open_gpu(drm: &LibDrmAmdgpu, pci_dev: &AMDGPU::PCI::PCI_BUS_INFO) -> Result<AmdGpu, Box<dyn Error>> {
+  let dev_path = pci_dev.get_drm_render_path()?;
 
   let (device, _, _) = {
     let fd = File::open(&dev_path)?;
@@ -128,5 +209,206 @@ pub fn active_gpu() -> Result<AmdGpu, Box<dyn Error>> {
     model: info.find_device_name_or_default().to_string(),
     family: info.get_family_name().to_string(),
     device_id: info.device_id(),
+    pci_bus_id: pci_dev.to_string(),
+    is_apu: info.is_apu(),
   })
 }
+
+/// Every AMD GPU visible to the DRM subsystem, iGPU and dGPU alike.
+pub fn all_gpus() -> Result<Vec<AmdGpu>, Box<dyn Error>> {
+  let drm = LibDrmAmdgpu::new().map_err(|_| "Could not initialize libdrm")?;
+  let pci_devs = AMDGPU::get_all_amdgpu_pci_bus();
+
+  if pci_devs.is_empty() {
+    return Err("No AMD GPU found".into());
+  }
+
+  pci_devs.iter().map(|pci_dev| open_gpu(&drm, pci_dev)).collect()
+}
+
+/// The AMD GPU most likely to be what the caller actually wants: a discrete
+/// card if one is present, falling back to whatever is first otherwise.
+pub fn active_gpu() -> Result<AmdGpu, Box<dyn Error>> {
+  let mut gpus = all_gpus()?;
+  let discrete_idx = gpus.iter().position(|gpu| !gpu.is_apu);
+
+  let idx = discrete_idx.unwrap_or(0);
+  Ok(gpus.swap_remove(idx))
+}
+
+#[cfg(feature = "fan_control")]
+fn locate_hwmon(render_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+  let render_name = render_path
+    .file_name()
+    .ok_or("render path has no file name")?;
+
+  let hwmon_dir = PathBuf::from("/sys/class/drm").join(render_name).join("device/hwmon");
+
+  let entry = fs::read_dir(&hwmon_dir)?
+    .next()
+    .ok_or_else(|| format!("no hwmon directory under {}", hwmon_dir.display()))??;
+
+  Ok(entry.path())
+}
+
+#[cfg(feature = "fan_control")]
+fn read_sysfs_u32(path: &Path) -> Result<u32, Box<dyn Error>> {
+  Ok(fs::read_to_string(path)?.trim().parse()?)
+}
+
+#[cfg(feature = "fan_control")]
+fn write_sysfs(path: &Path, value: u32) -> Result<(), Box<dyn Error>> {
+  fs::write(path, value.to_string())?;
+  Ok(())
+}
+
+/// Manual fan control over a card's hwmon interface, modeled on how amdgpud
+/// drives `pwm1`/`pwm1_enable`.
+#[cfg(feature = "fan_control")]
+pub struct AmdFanControl {
+  hwmon_path: PathBuf,
+  min_pwm: u8,
+  max_pwm: u8,
+  manual_enabled: bool,
+}
+
+#[cfg(feature = "fan_control")]
+impl AmdFanControl {
+  fn new(hwmon_path: PathBuf) -> Result<Self, Box<dyn Error>> {
+    let min_pwm = read_sysfs_u32(&hwmon_path.join("pwm1_min")).unwrap_or(0) as u8;
+    let max_pwm = read_sysfs_u32(&hwmon_path.join("pwm1_max")).unwrap_or(255) as u8;
+
+    Ok(Self {
+      hwmon_path,
+      min_pwm,
+      max_pwm,
+      manual_enabled: false,
+    })
+  }
+
+  fn enable_manual(&mut self) -> Result<(), Box<dyn Error>> {
+    if !self.manual_enabled {
+      write_sysfs(&self.hwmon_path.join("pwm1_enable"), 1)?;
+      self.manual_enabled = true;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(feature = "fan_control")]
+impl FanControl for AmdFanControl {
+  fn fan_rpm(&self) -> Option<u32> {
+    read_sysfs_u32(&self.hwmon_path.join("fan1_input")).ok()
+  }
+
+  fn pwm(&self) -> Option<u8> {
+    read_sysfs_u32(&self.hwmon_path.join("pwm1")).ok().map(|v| v as u8)
+  }
+
+  fn temps_c(&self) -> Vec<u32> {
+    (1..=8)
+      .filter_map(|n| read_sysfs_u32(&self.hwmon_path.join(format!("temp{n}_input"))).ok())
+      .map(|millidegrees| millidegrees / 1000)
+      .collect()
+  }
+
+  fn set_pwm(&mut self, pwm: u8) -> Result<(), Box<dyn Error>> {
+    self.enable_manual()?;
+    let clamped = pwm.clamp(self.min_pwm, self.max_pwm);
+    write_sysfs(&self.hwmon_path.join("pwm1"), clamped as u32)
+  }
+
+  fn set_fan_curve(&mut self, points: &[(u32, u8)]) -> Result<(), Box<dyn Error>> {
+    if points.is_empty() {
+      return Err("fan curve needs at least one point".into());
+    }
+
+    let hottest = self.temps_c().into_iter().max().unwrap_or(0);
+    self.set_pwm(interpolate_pwm(points, hottest))
+  }
+}
+
+/// Linearly interpolates the PWM target for `hottest` between the two curve
+/// points surrounding it. Below the lowest point, clamps to its PWM; above
+/// the highest, likewise. `points` need not be sorted or non-empty-checked
+/// by the caller beyond having at least one entry.
+#[cfg(feature = "fan_control")]
+fn interpolate_pwm(points: &[(u32, u8)], hottest: u32) -> u8 {
+  let mut sorted = points.to_vec();
+  sorted.sort_by_key(|(temp, _)| *temp);
+
+  let (lowest_temp, lowest_pwm) = sorted[0];
+  let (highest_temp, highest_pwm) = sorted[sorted.len() - 1];
+
+  if hottest <= lowest_temp {
+    return lowest_pwm;
+  }
+  if hottest >= highest_temp {
+    return highest_pwm;
+  }
+
+  let upper = sorted.iter().position(|(temp, _)| *temp >= hottest).unwrap();
+  let (t_lo, pwm_lo) = sorted[upper - 1];
+  let (t_hi, pwm_hi) = sorted[upper];
+
+  if t_hi == t_lo {
+    return pwm_lo;
+  }
+
+  let pwm_lo = pwm_lo as i32;
+  let pwm_hi = pwm_hi as i32;
+  let interpolated = pwm_lo + (hottest as i32 - t_lo as i32) * (pwm_hi - pwm_lo) / (t_hi as i32 - t_lo as i32);
+
+  interpolated.clamp(0, 255) as u8
+}
+
+#[cfg(all(test, feature = "fan_control"))]
+mod tests {
+  use super::interpolate_pwm;
+
+  #[test]
+  fn clamps_below_lowest_point() {
+    assert_eq!(interpolate_pwm(&[(40, 50), (80, 200)], 20), 50);
+  }
+
+  #[test]
+  fn clamps_above_highest_point() {
+    assert_eq!(interpolate_pwm(&[(40, 50), (80, 200)], 100), 200);
+  }
+
+  #[test]
+  fn interpolates_at_midpoint() {
+    assert_eq!(interpolate_pwm(&[(40, 50), (80, 200)], 60), 125);
+  }
+
+  #[test]
+  fn exact_hits_dont_need_interpolation() {
+    assert_eq!(interpolate_pwm(&[(40, 50), (60, 120), (80, 200)], 60), 120);
+  }
+
+  #[test]
+  fn equal_temp_points_take_the_first_pwm() {
+    assert_eq!(interpolate_pwm(&[(50, 50), (50, 200)], 50), 50);
+  }
+
+  #[test]
+  fn unsorted_points_are_sorted_before_interpolating() {
+    assert_eq!(interpolate_pwm(&[(80, 200), (40, 50)], 60), 125);
+  }
+
+  #[test]
+  fn single_point_always_wins() {
+    assert_eq!(interpolate_pwm(&[(50, 128)], 10), 128);
+    assert_eq!(interpolate_pwm(&[(50, 128)], 90), 128);
+  }
+}
+
+#[cfg(feature = "fan_control")]
+impl Drop for AmdFanControl {
+  fn drop(&mut self) {
+    if self.manual_enabled {
+      let _ = write_sysfs(&self.hwmon_path.join("pwm1_enable"), 2);
+    }
+  }
+}
@@ -0,0 +1,217 @@
+use std::{error::Error, fmt::Debug, rc::Rc};
+
+use nvml_wrapper::{
+  enum_wrappers::device::{Clock, TemperatureSensor},
+  Nvml,
+};
+
+#[cfg(feature = "fan_control")]
+use crate::FanControl;
+use crate::{Gpu, GpuInfo};
+
+pub struct NvidiaGpu {
+  nvml: Rc<Nvml>,
+  index: u32,
+
+  vendor: String,
+  model: String,
+  family: String,
+  device_id: u32,
+  pci_bus_id: String,
+}
+
+impl Debug for NvidiaGpu {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("NvidiaGpu")
+      .field("index", &self.index)
+      .field("vendor", &self.vendor)
+      .field("model", &self.model)
+      .field("family", &self.family)
+      .field("device_id", &self.device_id)
+      .field("pci_bus_id", &self.pci_bus_id)
+      .finish()
+  }
+}
+
+impl Gpu for NvidiaGpu {
+  fn vendor(&self) -> &str {
+    &self.vendor
+  }
+
+  fn model(&self) -> &str {
+    &self.model
+  }
+
+  fn family(&self) -> &str {
+    &self.family
+  }
+
+  fn device_id(&self) -> &u32 {
+    &self.device_id
+  }
+
+  fn pci_bus_id(&self) -> Option<&str> {
+    Some(&self.pci_bus_id)
+  }
+
+  #[cfg(feature = "gpu_info")]
+  fn info(&self) -> Box<dyn GpuInfo> {
+    Box::new(NvidiaGpuInfo {
+      nvml: self.nvml.clone(),
+      index: self.index,
+    })
+  }
+
+  #[cfg(feature = "fan_control")]
+  fn fan(&self) -> Result<Box<dyn FanControl>, Box<dyn Error>> {
+    Err("fan control is not implemented for NVIDIA GPUs".into())
+  }
+
+  #[cfg(feature = "snapshot")]
+  fn snapshot(&self) -> crate::snapshot::GpuSnapshot {
+    use crate::snapshot::{GpuMemorySnapshot, GpuSensorSnapshot, GpuSnapshot, MemoryHeap};
+
+    let device = self.nvml.device_by_index(self.index).ok();
+
+    let memory = GpuMemorySnapshot {
+      vram: device
+        .as_ref()
+        .and_then(|device| device.memory_info().ok())
+        .map(|meminfo| MemoryHeap {
+          total: Some(meminfo.total),
+          used: Some(meminfo.used),
+        })
+        .unwrap_or_default(),
+      // NVML doesn't distinguish a CPU-visible VRAM window or a GTT-style
+      // host-memory aperture the way amdgpu does.
+      visible_vram: MemoryHeap::default(),
+      gtt: MemoryHeap::default(),
+    };
+
+    let sensors = GpuSensorSnapshot {
+      load_pct: device
+        .as_ref()
+        .and_then(|device| device.utilization_rates().ok())
+        .map(|rates| rates.gpu),
+      temperature_c: device
+        .as_ref()
+        .and_then(|device| device.temperature(TemperatureSensor::Gpu).ok()),
+      power_usage_watts: device
+        .as_ref()
+        .and_then(|device| device.power_usage().ok())
+        .map(|milliwatts| milliwatts / 1000),
+      core_clock_mhz: device
+        .as_ref()
+        .and_then(|device| device.clock_info(Clock::Graphics).ok()),
+      memory_clock_mhz: device
+        .as_ref()
+        .and_then(|device| device.clock_info(Clock::Memory).ok()),
+    };
+
+    GpuSnapshot {
+      vendor: self.vendor.clone(),
+      model: self.model.clone(),
+      family: self.family.clone(),
+      device_id: self.device_id,
+      pci_bus_id: Some(self.pci_bus_id.clone()),
+      memory,
+      sensors,
+    }
+  }
+}
+
+#[cfg(feature = "gpu_info")]
+struct NvidiaGpuInfo {
+  nvml: Rc<Nvml>,
+  index: u32,
+}
+
+#[cfg(feature = "gpu_info")]
+impl GpuInfo for NvidiaGpuInfo {
+  fn total_vram(&self) -> u64 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.memory_info())
+      .map(|meminfo| meminfo.total)
+      .unwrap_or(0)
+  }
+
+  fn used_vram(&self) -> u64 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.memory_info())
+      .map(|meminfo| meminfo.used)
+      .unwrap_or(0)
+  }
+
+  fn load_pct(&self) -> u32 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.utilization_rates())
+      .map(|rates| rates.gpu)
+      .unwrap_or(0)
+  }
+
+  fn temperature(&self) -> u32 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.temperature(TemperatureSensor::Gpu))
+      .unwrap_or(0)
+  }
+
+  fn power_usage_watts(&self) -> u32 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.power_usage())
+      .map(|milliwatts| milliwatts / 1000)
+      .unwrap_or(0)
+  }
+
+  fn core_clock_mhz(&self) -> u32 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.clock_info(Clock::Graphics))
+      .unwrap_or(0)
+  }
+
+  fn memory_clock_mhz(&self) -> u32 {
+    self
+      .nvml
+      .device_by_index(self.index)
+      .and_then(|device| device.clock_info(Clock::Memory))
+      .unwrap_or(0)
+  }
+}
+
+/// Every NVIDIA GPU NVML can see.
+pub fn all_gpus() -> Result<Vec<NvidiaGpu>, Box<dyn Error>> {
+  let nvml = Rc::new(Nvml::init()?);
+  let count = nvml.device_count()?;
+
+  (0..count)
+    .map(|index| {
+      let device = nvml.device_by_index(index)?;
+      let pci_info = device.pci_info()?;
+
+      Ok(NvidiaGpu {
+        nvml: nvml.clone(),
+        index,
+
+        vendor: "NVIDIA".to_string(),
+        model: device.name()?,
+        family: device
+          .architecture()
+          .map(|arch| format!("{arch:?}"))
+          .unwrap_or_default(),
+        device_id: pci_info.pci_device_id,
+        pci_bus_id: pci_info.bus_id,
+      })
+    })
+    .collect()
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+/// A single memory heap's total/used size. Fields are `None` rather than `0`
+/// when the backend couldn't read the heap at all, so a caller can tell
+/// "missing" apart from "empty".
+#[derive(Debug, Default, Serialize)]
+pub struct MemoryHeap {
+  pub total: Option<u64>,
+  pub used: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GpuMemorySnapshot {
+  pub vram: MemoryHeap,
+  pub visible_vram: MemoryHeap,
+  pub gtt: MemoryHeap,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GpuSensorSnapshot {
+  pub load_pct: Option<u32>,
+  pub temperature_c: Option<u32>,
+  pub power_usage_watts: Option<u32>,
+  pub core_clock_mhz: Option<u32>,
+  pub memory_clock_mhz: Option<u32>,
+}
+
+/// A single timestamped-by-the-caller record of everything a backend can
+/// read about a device, meant to be dumped to JSON when a tool hits an
+/// anomaly and wants one self-contained description of the GPU's state
+/// rather than a pile of individual getter calls.
+#[derive(Debug, Serialize)]
+pub struct GpuSnapshot {
+  pub vendor: String,
+  pub model: String,
+  pub family: String,
+  pub device_id: u32,
+  pub pci_bus_id: Option<String>,
+  pub memory: GpuMemorySnapshot,
+  pub sensors: GpuSensorSnapshot,
+}